@@ -0,0 +1,83 @@
+/*
+ * This file is part of 'The Hundred Years War'.
+ * 'The Hundred Years War' is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation,
+ * either version 3 of the License, or (at your option) any later version.
+ * 'The Hundred Years War' is distributed in the hope that it will be useful, but WITHOUT
+ * ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or 
+ * FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for 
+ * more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with 'The Hundred Years War'. If not, see <https://www.gnu.org/licenses/>. 
+ *
+ */
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+///
+/// The name of the application's subdirectory in the user cache directory
+///
+const CACHE_DIR_NAME: &str = "war";
+
+///
+/// Returns the directory processed assets are cached in, or None if the user cache
+/// directory could not be determined
+///
+pub fn user_cache_dir() -> Option<PathBuf> {
+    let base = if let Some(path) = absolute_path_from_env("XDG_CACHE_HOME") {
+	path
+    } else if let Some(path) = absolute_path_from_env("LOCALAPPDATA") {
+	path
+    } else if let Some(path) = absolute_path_from_env("HOME") {
+	path.join(".cache")
+    } else {
+	return None;
+    };
+    Some(base.join(CACHE_DIR_NAME))
+}
+
+///
+/// Returns the path in the specified environment variable, or None if the variable is
+/// not set, empty or not an absolute path
+///
+fn absolute_path_from_env(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(env::var_os(name)?);
+    if path.is_absolute() {
+	Some(path)
+    } else {
+	None
+    }
+}
+
+///
+/// Removes all cached assets from the cache directory
+///
+pub fn clear(cache_dir: &Path) -> Result<(), Error> {
+    if !cache_dir.is_absolute() {
+	return Err(Error::RelativePath(cache_dir.to_path_buf()));
+    }
+    match fs::remove_dir_all(cache_dir) {
+	Ok(()) => Ok(()),
+	Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+	Err(e) => Err(Error::IO(e.kind())),
+    }
+}
+
+///
+/// Errors that can occur accessing the cache
+///
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    ///
+    /// An IO error occurred
+    ///
+    IO(io::ErrorKind),
+    ///
+    /// The cache directory is not an absolute path
+    ///
+    RelativePath(PathBuf),
+}
@@ -13,6 +13,7 @@
  *
  */
 
+mod cache;
 mod settings;
 
 use settings::Settings;
@@ -21,12 +22,22 @@ use settings::Settings;
 /// Main application entry point
 ///
 fn main() {
-    let _settings = match Settings::load() {
+    let settings = match Settings::load() {
 	Ok(s) => s,
 	Err(e) => {
 	    println!("Could not load setting: {:?}", e);
 	    Settings::default()
 	},
     };
+    if settings.clear_cache() {
+	match settings.cache_dir() {
+	    Some(cache_dir) => {
+		if let Err(e) = cache::clear(cache_dir) {
+		    println!("Could not clear cache: {:?}", e);
+		}
+	    },
+	    None => println!("Could not clear cache: no cache directory found"),
+	}
+    }
 }
 
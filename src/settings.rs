@@ -13,7 +13,11 @@
  *
  */
 
-use clap::{Parser, ValueEnum};
+use crate::cache;
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, ValueEnum};
+use std::path::{Path, PathBuf};
 
 ///
 /// Models application settings
@@ -24,6 +28,51 @@ pub struct Settings {
     /// The log level
     ///
     log_level: LogLevel,
+    ///
+    /// How strongly scripted historical decisions steer the campaign
+    ///
+    historical_flavor: HistoricalFlavor,
+    ///
+    /// The selected graphics quality preset
+    ///
+    graphics_quality: GraphicsQuality,
+    ///
+    /// The graphics options currently in effect
+    ///
+    graphics_options: GraphicsOptions,
+    ///
+    /// The graphics options of the custom preset, i.e. the last manual tweaks
+    ///
+    custom_graphics_options: GraphicsOptions,
+    ///
+    /// The maximum number of frames per second, independent of vsync, or None if unlimited
+    ///
+    fps_limit: Option<u32>,
+    ///
+    /// The maximum number of frames per second while the window is unfocused or minimized,
+    /// or None if rendering should not be throttled
+    ///
+    background_fps_limit: Option<u32>,
+    ///
+    /// The directory processed assets are cached in
+    ///
+    cache_dir: Option<PathBuf>,
+    ///
+    /// Whether the cache should be cleared on startup
+    ///
+    clear_cache: bool,
+    ///
+    /// Whether data validation errors are fatal or downgraded to warnings
+    ///
+    data_validation: DataValidation,
+    ///
+    /// The UI scale factor, or None if it should be derived from the display DPI
+    ///
+    ui_scale: Option<f32>,
+    ///
+    /// The number of simulation ticks per second at normal speed
+    ///
+    tick_rate: u32,
 }
 
 impl Settings {
@@ -45,8 +94,71 @@ impl Settings {
 	if let Some(log_level) = cli_settings.log_level {
 	    self.log_level = log_level;
 	}
+	if let Some(historical_flavor) = cli_settings.historical_flavor {
+	    self.historical_flavor = historical_flavor;
+	}
+	if let Some(graphics_quality) = cli_settings.graphics_quality {
+	    self.set_graphics_quality(graphics_quality);
+	}
+	if !cli_settings.graphics_options.is_empty() {
+	    self.tweak_graphics_options(|options| cli_settings.graphics_options.apply(options));
+	}
+	if let Some(fps_limit) = cli_settings.fps_limit {
+	    self.fps_limit = frame_limit(fps_limit);
+	}
+	if let Some(background_fps_limit) = cli_settings.background_fps_limit {
+	    self.background_fps_limit = frame_limit(background_fps_limit);
+	}
+	self.clear_cache = cli_settings.clear_cache;
+	if let Some(data_validation) = cli_settings.data_validation {
+	    self.data_validation = data_validation;
+	}
+	if let Some(ui_scale) = cli_settings.ui_scale {
+	    self.ui_scale = Some(ui_scale);
+	}
+	if let Some(tick_rate) = cli_settings.tick_rate {
+	    self.tick_rate = tick_rate;
+	}
 	Ok(())
     }
+
+    ///
+    /// Returns the directory processed assets are cached in
+    ///
+    pub fn cache_dir(&self) -> Option<&Path> {
+	self.cache_dir.as_deref()
+    }
+
+    ///
+    /// Returns true if the cache should be cleared on startup
+    ///
+    pub fn clear_cache(&self) -> bool {
+	self.clear_cache
+    }
+
+    ///
+    /// Switches to a graphics quality preset, replacing all graphics options at once
+    /// Switching to the custom preset restores the last manual tweaks
+    ///
+    pub fn set_graphics_quality(&mut self, graphics_quality: GraphicsQuality) {
+	self.graphics_options = match graphics_quality.options() {
+	    Some(options) => options,
+	    None => self.custom_graphics_options.clone(),
+	};
+	self.graphics_quality = graphics_quality;
+    }
+
+    ///
+    /// Manually tweaks the current graphics options and switches to the custom preset,
+    /// which remembers the tweaked options
+    ///
+    pub fn tweak_graphics_options<F: FnOnce(&mut GraphicsOptions)>(&mut self, tweak: F) {
+	let mut options = self.graphics_options.clone();
+	tweak(&mut options);
+	self.custom_graphics_options = options.clone();
+	self.graphics_options = options;
+	self.graphics_quality = GraphicsQuality::Custom;
+    }
 }
 
 impl Default for Settings {
@@ -54,12 +166,37 @@ impl Default for Settings {
     /// Returns the default settings
     ///
     fn default() -> Settings {
+	let graphics_options = GraphicsQuality::High
+	    .options()
+	    .expect("the high quality preset has graphics options");
 	Settings {
 	    log_level: LogLevel::Warning,
+	    historical_flavor: HistoricalFlavor::Plausible,
+	    graphics_quality: GraphicsQuality::High,
+	    custom_graphics_options: graphics_options.clone(),
+	    graphics_options,
+	    fps_limit: None,
+	    background_fps_limit: Some(15),
+	    cache_dir: cache::user_cache_dir(),
+	    clear_cache: false,
+	    data_validation: DataValidation::Lenient,
+	    ui_scale: None,
+	    tick_rate: 10,
 	}
     }
 }
 
+///
+/// Converts a frame limit specified in a configuration, where 0 means unlimited
+///
+fn frame_limit(fps: u32) -> Option<u32> {
+    if fps == 0 {
+	None
+    } else {
+	Some(fps)
+    }
+}
+
 ///
 /// Models the log level of the application
 ///
@@ -83,6 +220,159 @@ pub enum LogLevel {
     Error,
 }
 
+///
+/// Models how scripted historical AI decisions (alliance shifts, revolts, ...)
+/// are applied during a campaign
+///
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum HistoricalFlavor {
+    ///
+    /// Scripted historical decisions never fire
+    ///
+    Off,
+    ///
+    /// Scripted historical decisions fire at their historical date unless
+    /// the world has diverged too far from history
+    ///
+    Plausible,
+    ///
+    /// Scripted historical decisions always fire at their historical date
+    ///
+    Railroaded,
+}
+
+///
+/// Models the graphics quality presets
+///
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum GraphicsQuality {
+    ///
+    /// For low end machines
+    ///
+    Low,
+    ///
+    /// Balances quality and performance
+    ///
+    Medium,
+    ///
+    /// High quality
+    ///
+    High,
+    ///
+    /// Everything at maximum quality
+    ///
+    Ultra,
+    ///
+    /// Manually tweaked options
+    ///
+    Custom,
+}
+
+impl GraphicsQuality {
+    ///
+    /// Returns the graphics options of this preset, or None for the custom preset
+    ///
+    fn options(&self) -> Option<GraphicsOptions> {
+	match self {
+	    GraphicsQuality::Low => Some(GraphicsOptions {
+		msaa_samples: 0,
+		texture_downscale: 4,
+		max_particles: 250,
+		shadow_detail: Detail::Off,
+		terrain_detail: Detail::Low,
+	    }),
+	    GraphicsQuality::Medium => Some(GraphicsOptions {
+		msaa_samples: 2,
+		texture_downscale: 2,
+		max_particles: 1000,
+		shadow_detail: Detail::Low,
+		terrain_detail: Detail::Medium,
+	    }),
+	    GraphicsQuality::High => Some(GraphicsOptions {
+		msaa_samples: 4,
+		texture_downscale: 1,
+		max_particles: 4000,
+		shadow_detail: Detail::Medium,
+		terrain_detail: Detail::High,
+	    }),
+	    GraphicsQuality::Ultra => Some(GraphicsOptions {
+		msaa_samples: 8,
+		texture_downscale: 1,
+		max_particles: 16000,
+		shadow_detail: Detail::High,
+		terrain_detail: Detail::High,
+	    }),
+	    GraphicsQuality::Custom => None,
+	}
+    }
+}
+
+///
+/// Models the concrete graphics options a quality preset maps to
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphicsOptions {
+    ///
+    /// The number of multisample anti-aliasing samples, 0 disables MSAA
+    ///
+    pub msaa_samples: u8,
+    ///
+    /// The factor by which textures are downscaled, 1 keeps full resolution
+    ///
+    pub texture_downscale: u8,
+    ///
+    /// The maximum number of live particles
+    ///
+    pub max_particles: u32,
+    ///
+    /// The level of detail of shadows
+    ///
+    pub shadow_detail: Detail,
+    ///
+    /// The level of detail of the terrain
+    ///
+    pub terrain_detail: Detail,
+}
+
+///
+/// Models the level of detail of a graphical feature
+///
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum Detail {
+    ///
+    /// The feature is disabled
+    ///
+    Off,
+    ///
+    /// Low detail
+    ///
+    Low,
+    ///
+    /// Medium detail
+    ///
+    Medium,
+    ///
+    /// High detail
+    ///
+    High,
+}
+
+///
+/// Models how validation errors in data files are handled
+///
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+pub enum DataValidation {
+    ///
+    /// Validation errors are fatal, intended for development
+    ///
+    Strict,
+    ///
+    /// Validation errors are logged as warnings and sensible defaults are used instead,
+    /// so a single broken mod file does not block launching the game
+    ///
+    Lenient,
+}
+
 ///
 /// Errors that can occur loading or saving settings
 ///
@@ -100,4 +390,136 @@ pub struct CLISettingsConfiguration {
     ///
     #[arg(short, long, value_name = "LOG LEVEL")]
     log_level: Option<LogLevel>,
+    ///
+    /// How strongly scripted historical decisions steer the campaign
+    ///
+    #[arg(long, value_name = "HISTORICAL FLAVOR")]
+    historical_flavor: Option<HistoricalFlavor>,
+    ///
+    /// The graphics quality preset
+    ///
+    #[arg(short, long, value_name = "GRAPHICS QUALITY")]
+    graphics_quality: Option<GraphicsQuality>,
+    ///
+    /// Manual tweaks of the graphics options
+    ///
+    #[command(flatten)]
+    graphics_options: CLIGraphicsOptionsConfiguration,
+    ///
+    /// The maximum number of frames per second, 0 for unlimited
+    ///
+    #[arg(long, value_name = "FPS")]
+    fps_limit: Option<u32>,
+    ///
+    /// The maximum number of frames per second while the window is unfocused or minimized,
+    /// 0 for unlimited
+    ///
+    #[arg(long, value_name = "FPS")]
+    background_fps_limit: Option<u32>,
+    ///
+    /// Clears the cache of processed assets on startup
+    ///
+    #[arg(long)]
+    clear_cache: bool,
+    ///
+    /// Whether data validation errors are fatal or downgraded to warnings
+    ///
+    #[arg(long, value_name = "DATA VALIDATION")]
+    data_validation: Option<DataValidation>,
+    ///
+    /// Overrides the UI scale factor derived from the display DPI
+    ///
+    #[arg(long, value_name = "SCALE", allow_negative_numbers = true, value_parser = parse_ui_scale)]
+    ui_scale: Option<f32>,
+    ///
+    /// The number of simulation ticks per second at normal speed
+    ///
+    #[arg(long, value_name = "TICKS", value_parser = clap::value_parser!(u32).range(1..))]
+    tick_rate: Option<u32>,
+}
+
+///
+/// Parses a UI scale factor, which must be finite and strictly positive
+///
+fn parse_ui_scale(value: &str) -> Result<f32, String> {
+    let ui_scale: f32 = value.parse().map_err(|e| format!("{}", e))?;
+    if ui_scale.is_finite() && ui_scale > 0.0 {
+	Ok(ui_scale)
+    } else {
+	Err(format!("{} is not a finite, strictly positive number", value))
+    }
+}
+
+///
+/// The command line model of manual graphics option tweaks
+///
+#[derive(Args)]
+pub struct CLIGraphicsOptionsConfiguration {
+    ///
+    /// The number of multisample anti-aliasing samples, 0 disables MSAA
+    ///
+    #[arg(long, value_name = "SAMPLES", value_parser = msaa_samples_parser())]
+    msaa_samples: Option<u8>,
+    ///
+    /// The factor by which textures are downscaled, 1 keeps full resolution
+    ///
+    #[arg(long, value_name = "FACTOR", value_parser = clap::value_parser!(u8).range(1..=8))]
+    texture_downscale: Option<u8>,
+    ///
+    /// The maximum number of live particles
+    ///
+    #[arg(long, value_name = "PARTICLES")]
+    max_particles: Option<u32>,
+    ///
+    /// The level of detail of shadows
+    ///
+    #[arg(long, value_name = "DETAIL")]
+    shadow_detail: Option<Detail>,
+    ///
+    /// The level of detail of the terrain
+    ///
+    #[arg(long, value_name = "DETAIL")]
+    terrain_detail: Option<Detail>,
+}
+
+///
+/// Returns a parser that only accepts valid multisample anti-aliasing sample counts
+///
+fn msaa_samples_parser() -> impl TypedValueParser<Value = u8> {
+    PossibleValuesParser::new(["0", "2", "4", "8", "16"])
+	.map(|samples| samples.parse().expect("possible values are valid sample counts"))
+}
+
+impl CLIGraphicsOptionsConfiguration {
+    ///
+    /// Returns true if no graphics option was specified on the command line
+    ///
+    fn is_empty(&self) -> bool {
+	self.msaa_samples.is_none()
+	    && self.texture_downscale.is_none()
+	    && self.max_particles.is_none()
+	    && self.shadow_detail.is_none()
+	    && self.terrain_detail.is_none()
+    }
+
+    ///
+    /// Applies the specified graphics options on top of the given options
+    ///
+    fn apply(&self, options: &mut GraphicsOptions) {
+	if let Some(msaa_samples) = self.msaa_samples {
+	    options.msaa_samples = msaa_samples;
+	}
+	if let Some(texture_downscale) = self.texture_downscale {
+	    options.texture_downscale = texture_downscale;
+	}
+	if let Some(max_particles) = self.max_particles {
+	    options.max_particles = max_particles;
+	}
+	if let Some(shadow_detail) = &self.shadow_detail {
+	    options.shadow_detail = shadow_detail.clone();
+	}
+	if let Some(terrain_detail) = &self.terrain_detail {
+	    options.terrain_detail = terrain_detail.clone();
+	}
+    }
 }